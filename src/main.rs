@@ -1,17 +1,28 @@
 use clap::{ArgGroup, Parser};
 use datafusion::arrow::csv::writer::WriterBuilder;
-use datafusion::arrow::error::Result;
+use datafusion::arrow::json::{ArrayWriter, LineDelimitedWriter};
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::common::TableReference;
+use datafusion::dataframe::DataFrameWriteOptions;
 use datafusion::execution::context::SessionState;
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion::prelude::*;
 use dbase::DbaseTableFactory;
 use dirs::home_dir;
+use futures::{FutureExt, StreamExt};
+use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::highlight::Highlighter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{CompletionType, Config, Context as RustylineContext, Editor, Helper, Hinter};
+use serde::Serialize;
+use std::borrow::Cow;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, IsTerminal};
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -34,6 +45,29 @@ struct Args {
 
     #[arg(long)]
     delimiter_for_dsv: Option<String>,
+
+    /// Directory of `.sql` files to run as a non-interactive benchmark instead
+    /// of reading a query from `-e`/`-f` or starting the REPL.
+    #[arg(long)]
+    query_path: Option<String>,
+
+    /// Directory of `.dbf` files to register as tables before running the
+    /// benchmark queries. Table names are the file stem, e.g. `customers.dbf`
+    /// becomes `customers`.
+    #[arg(long)]
+    data_path: Option<String>,
+
+    /// Number of times to run each benchmark query.
+    #[arg(long, default_value_t = 1)]
+    iterations: usize,
+
+    /// Path to write the benchmark's JSON report to. Defaults to stdout.
+    #[arg(long)]
+    output: Option<String>,
+
+    /// Destination file for `--output-format parquet`.
+    #[arg(long)]
+    output_file: Option<String>,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -42,11 +76,306 @@ enum OutputFormatArg {
     Tsv,
     Dsv,
     Table,
+    Json,
+    NdJson,
+    Parquet,
+    Automatic,
 }
 
 enum OutputFormat {
     Delimited(u8),
     Table,
+    Json,
+    NdJson,
+    Parquet(String),
+}
+
+const SQL_KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "GROUP", "BY", "ORDER", "LIMIT", "OFFSET", "JOIN", "LEFT", "RIGHT",
+    "INNER", "OUTER", "FULL", "ON", "AS", "INSERT", "INTO", "VALUES", "UPDATE", "SET", "DELETE",
+    "CREATE", "EXTERNAL", "TABLE", "STORED", "LOCATION", "DROP", "AND", "OR", "NOT", "NULL", "IS",
+    "DISTINCT", "HAVING", "UNION", "ALL", "ASC", "DESC", "LIKE", "IN", "BETWEEN", "CAST", "COUNT",
+    "SUM", "AVG", "MIN", "MAX", "CASE", "WHEN", "THEN", "ELSE", "END",
+];
+
+/// The REPL's `rustyline` helper: validates multi-line input and completes SQL keywords, tables, and columns.
+#[derive(Hinter)]
+struct ReplHelper {
+    ctx: SessionContext,
+}
+
+impl ReplHelper {
+    fn new(ctx: SessionContext) -> Self {
+        Self { ctx }
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+
+        for catalog_name in self.ctx.catalog_names() {
+            let Some(catalog) = self.ctx.catalog(&catalog_name) else {
+                continue;
+            };
+            for schema_name in catalog.schema_names() {
+                if let Some(schema) = catalog.schema(&schema_name) {
+                    names.extend(schema.table_names());
+                }
+            }
+        }
+
+        names
+    }
+
+    // `Completer::complete` is synchronous and runs on every tab-press, so this
+    // can't block on the lookup (that would hang the REPL the moment a
+    // catalog's `table()` needs real async I/O, e.g. an object-store-backed
+    // one). `now_or_never` polls the future once and gives up instead of
+    // blocking, so a lookup that isn't already resolved just yields no
+    // column completions for that keystroke.
+    fn columns_for_table(&self, table: &str) -> Vec<String> {
+        self.ctx
+            .table(table)
+            .now_or_never()
+            .and_then(|result| result.ok())
+            .map(|df| {
+                df.schema()
+                    .fields()
+                    .iter()
+                    .map(|field| field.name().clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Helper for ReplHelper {}
+
+impl Validator for ReplHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        let input = ctx.input();
+        if parse_load_command(input).is_some() || ends_with_terminated_statement(input) {
+            Ok(ValidationResult::Valid(None))
+        } else {
+            Ok(ValidationResult::Incomplete)
+        }
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &RustylineContext<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos]
+            .rfind(|c: char| c.is_whitespace() || c == '(' || c == ',' || c == '.')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let word = &line[start..pos];
+
+        if word.is_empty() {
+            return Ok((start, Vec::new()));
+        }
+
+        let word_lower = word.to_lowercase();
+        let mut candidates: Vec<String> = SQL_KEYWORDS
+            .iter()
+            .filter(|keyword| keyword.to_lowercase().starts_with(&word_lower))
+            .map(|keyword| keyword.to_string())
+            .collect();
+
+        let tables = self.table_names();
+        candidates.extend(
+            tables
+                .iter()
+                .filter(|table| table.to_lowercase().starts_with(&word_lower))
+                .cloned(),
+        );
+
+        for table in referenced_tables(&line[..pos], &tables) {
+            candidates.extend(
+                self.columns_for_table(&table)
+                    .into_iter()
+                    .filter(|column| column.to_lowercase().starts_with(&word_lower)),
+            );
+        }
+
+        candidates.sort();
+        candidates.dedup();
+
+        let pairs = candidates
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+
+        Ok((start, pairs))
+    }
+}
+
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        highlight_keywords(line)
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        true
+    }
+}
+
+/// Wraps whole-word, case-insensitive matches of `SQL_KEYWORDS` in bold so
+/// they stand out against dBase's otherwise cryptic fixed-width field names.
+fn highlight_keywords(line: &str) -> Cow<'_, str> {
+    let mut out = String::with_capacity(line.len());
+    let mut word_start = 0;
+
+    let mut flush_word = |out: &mut String, word: &str| {
+        let is_keyword = SQL_KEYWORDS
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(word));
+        if is_keyword {
+            out.push_str("\x1b[1m");
+            out.push_str(word);
+            out.push_str("\x1b[0m");
+        } else {
+            out.push_str(word);
+        }
+    };
+
+    for (i, c) in line.char_indices() {
+        if !(c.is_alphanumeric() || c == '_') {
+            if i > word_start {
+                flush_word(&mut out, &line[word_start..i]);
+            }
+            out.push(c);
+            word_start = i + c.len_utf8();
+        }
+    }
+    if word_start < line.len() {
+        flush_word(&mut out, &line[word_start..]);
+    }
+
+    Cow::Owned(out)
+}
+
+/// Finds which already-registered tables are mentioned (as whole words) in
+/// `buffer`, so their columns can be offered as completions too.
+fn referenced_tables(buffer: &str, known_tables: &[String]) -> Vec<String> {
+    let lower = buffer.to_lowercase();
+
+    known_tables
+        .iter()
+        .filter(|table| {
+            let needle = table.to_lowercase();
+            lower.match_indices(&needle).any(|(start, _)| {
+                let before_ok = lower[..start]
+                    .chars()
+                    .next_back()
+                    .map(|c| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(true);
+                let end = start + needle.len();
+                let after_ok = lower[end..]
+                    .chars()
+                    .next()
+                    .map(|c| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(true);
+                before_ok && after_ok
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Parses the REPL's `load <path> as <table>` meta-command.
+fn parse_load_command(input: &str) -> Option<(String, String)> {
+    let rest = input.trim().strip_prefix("load")?;
+    let rest = rest.strip_prefix(char::is_whitespace)?.trim();
+    let (path, rest) = rest.split_once(char::is_whitespace)?;
+    let name = rest.trim().strip_prefix("as")?.trim();
+
+    if path.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some((path.to_string(), name.to_string()))
+}
+
+async fn load_table(ctx: &SessionContext, path: &str, name: &str) -> datafusion::error::Result<()> {
+    let create_stmt = format!("CREATE EXTERNAL TABLE \"{}\" STORED AS DBASE LOCATION '{}'", name, path);
+    ctx.sql(&create_stmt).await?.collect().await?;
+    println!("loaded '{}' as table \"{}\"", path, name);
+    Ok(())
+}
+
+fn ends_with_terminated_statement(input: &str) -> bool {
+    let chars: Vec<char> = input.chars().collect();
+    let mut in_single_quote = false;
+    let mut in_double_quote = false;
+    let mut in_line_comment = false;
+    let mut in_block_comment = false;
+    let mut last_significant_was_semicolon = false;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if in_line_comment {
+            if c == '\n' {
+                in_line_comment = false;
+            }
+            i += 1;
+            continue;
+        }
+
+        if in_block_comment {
+            if c == '*' && chars.get(i + 1) == Some(&'/') {
+                in_block_comment = false;
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+
+        match c {
+            '\'' if !in_double_quote => {
+                in_single_quote = !in_single_quote;
+                last_significant_was_semicolon = false;
+            }
+            '"' if !in_single_quote => {
+                in_double_quote = !in_double_quote;
+                last_significant_was_semicolon = false;
+            }
+            '-' if !in_single_quote && !in_double_quote && chars.get(i + 1) == Some(&'-') => {
+                in_line_comment = true;
+                i += 2;
+                continue;
+            }
+            '/' if !in_single_quote && !in_double_quote && chars.get(i + 1) == Some(&'*') => {
+                in_block_comment = true;
+                i += 2;
+                continue;
+            }
+            ';' if !in_single_quote && !in_double_quote => {
+                last_significant_was_semicolon = true;
+            }
+            c if c.is_whitespace() => {}
+            _ => last_significant_was_semicolon = false,
+        }
+
+        i += 1;
+    }
+
+    last_significant_was_semicolon
+        && !in_single_quote
+        && !in_double_quote
+        && !in_line_comment
+        && !in_block_comment
 }
 
 #[tokio::main]
@@ -72,18 +401,39 @@ async fn main() -> datafusion::error::Result<()> {
     let ctx = SessionContext::with_state(state);
 
     let output_format = match args.output_format {
-        Some(c) => match c {
-            OutputFormatArg::Csv => OutputFormat::Delimited(b','),
-            OutputFormatArg::Tsv => OutputFormat::Delimited(b'\t'),
-            OutputFormatArg::Dsv => match &args.delimiter_for_dsv {
-                Some(s) => OutputFormat::Delimited(s.as_bytes()[0]),
-                None => OutputFormat::Delimited(b'|'),
-            },
-            OutputFormatArg::Table => OutputFormat::Table,
+        Some(OutputFormatArg::Csv) => OutputFormat::Delimited(b','),
+        Some(OutputFormatArg::Tsv) => OutputFormat::Delimited(b'\t'),
+        Some(OutputFormatArg::Dsv) => match &args.delimiter_for_dsv {
+            Some(s) => OutputFormat::Delimited(s.as_bytes()[0]),
+            None => OutputFormat::Delimited(b'|'),
+        },
+        Some(OutputFormatArg::Table) => OutputFormat::Table,
+        Some(OutputFormatArg::Json) => OutputFormat::Json,
+        Some(OutputFormatArg::NdJson) => OutputFormat::NdJson,
+        Some(OutputFormatArg::Parquet) => match &args.output_file {
+            Some(path) => OutputFormat::Parquet(path.clone()),
+            None => panic!("--output-file is required for --output-format parquet"),
         },
-        None => OutputFormat::Table,
+        Some(OutputFormatArg::Automatic) | None => {
+            if std::io::stdout().is_terminal() {
+                OutputFormat::Table
+            } else {
+                OutputFormat::Delimited(b',')
+            }
+        }
     };
 
+    if let Some(query_path) = &args.query_path {
+        return run_benchmark(
+            &ctx,
+            query_path,
+            args.data_path.as_deref(),
+            args.iterations,
+            args.output.as_deref(),
+        )
+        .await;
+    }
+
     match (args.execute, args.file) {
         // query provided directly
         (Some(q), None) => {
@@ -116,50 +466,239 @@ async fn process_statements(
     ctx: &SessionContext,
     query: &str,
     output_format: &OutputFormat,
-) -> datafusion::error::Result<()> {
+) -> datafusion::error::Result<usize> {
     let statements: Vec<&str> = query
         .split(';')
         .filter(|statement| !statement.trim().is_empty())
         .collect();
 
+    let mut row_count = 0;
     for statement in statements {
         match process_statement(ctx, statement, output_format).await {
-            Ok(_) => continue,
+            Ok(rows) => row_count += rows,
             Err(e) => {
                 println!("{}", e);
                 break;
             }
         }
     }
-    Ok(())
+    Ok(row_count)
 }
 
 async fn process_statement(
     ctx: &SessionContext,
     statement: &str,
     output_format: &OutputFormat,
+) -> datafusion::error::Result<usize> {
+    autoload_dbf_tables(ctx, statement).await?;
+
+    let df = ctx.sql(statement).await?;
+
+    // Parquet is written by the DataFrame itself rather than driven off a
+    // result stream, so it's handled before the stream-based formats below.
+    if let OutputFormat::Parquet(path) = output_format {
+        let written = df.write_parquet(path, DataFrameWriteOptions::new(), None).await?;
+        return Ok(written.iter().map(|batch| batch.num_rows()).sum());
+    }
+
+    let stream = df.execute_stream().await?;
+
+    match output_format {
+        OutputFormat::Delimited(s) => write_delimited_stream(stream, *s).await,
+        OutputFormat::Table => write_table_stream(stream).await,
+        OutputFormat::Json => write_json_stream(stream, false).await,
+        OutputFormat::NdJson => write_json_stream(stream, true).await,
+        OutputFormat::Parquet(_) => unreachable!("handled above"),
+    }
+}
+
+/// Registers any unresolved `.dbf` table references in `statement` before it's planned.
+///
+/// This is a raw-text pre-scan rather than a `SchemaProvider`/catalog hook, so it only
+/// sees quoted `.dbf` literals written immediately after `FROM`/`JOIN` (a subquery's
+/// own `FROM` included); a `.dbf` reference in any other position, such as after a
+/// comma in a `FROM` list, is not registered.
+async fn autoload_dbf_tables(ctx: &SessionContext, statement: &str) -> datafusion::error::Result<()> {
+    for path in find_dbf_references(statement) {
+        // The table was (or will be) registered as a single quoted identifier
+        // containing a literal dot, e.g. `CREATE EXTERNAL TABLE "customers.dbf"`.
+        // `TableReference::bare` matches that; the default `&str` conversion
+        // would instead split on the dot into a two-part schema.table
+        // reference that never matches what was actually registered.
+        if ctx.table_exist(TableReference::bare(path.clone()))? {
+            continue;
+        }
+
+        let create_stmt = format!("CREATE EXTERNAL TABLE \"{0}\" STORED AS DBASE LOCATION '{0}'", path);
+        ctx.sql(&create_stmt).await?.collect().await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct BenchmarkQueryReport {
+    id: usize,
+    name: String,
+    iterations: usize,
+    durations_ms: Vec<f64>,
+    row_counts: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct BenchmarkReport {
+    engine_version: String,
+    queries: Vec<BenchmarkQueryReport>,
+}
+
+/// Runs every `.sql` file under `query_path` for `iterations` rounds and writes a JSON timing report.
+async fn run_benchmark(
+    ctx: &SessionContext,
+    query_path: &str,
+    data_path: Option<&str>,
+    iterations: usize,
+    output: Option<&str>,
 ) -> datafusion::error::Result<()> {
-    let res = ctx.sql(statement).await?;
+    if let Some(data_path) = data_path {
+        load_dbf_directory(ctx, data_path).await?;
+    }
 
-    match &output_format {
-        OutputFormat::Delimited(s) => {
-            let results = res.collect().await?;
-            print_results(&results, *s).unwrap();
+    let mut query_files: Vec<_> = std::fs::read_dir(query_path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "sql").unwrap_or(false))
+        .collect();
+    query_files.sort();
+
+    let mut queries = Vec::with_capacity(query_files.len());
+
+    for (id, path) in query_files.iter().enumerate() {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("query")
+            .to_string();
+        let sql = std::fs::read_to_string(path)?;
+
+        let mut durations_ms = Vec::with_capacity(iterations);
+        let mut row_counts = Vec::with_capacity(iterations);
+
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let rows = execute_and_count_rows(ctx, &sql).await?;
+            durations_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+            row_counts.push(rows);
         }
-        OutputFormat::Table => {
-            // todo: don't collect the result twice
-            if !res.clone().collect().await?.is_empty() {
-                res.show().await?;
-            }
+
+        queries.push(BenchmarkQueryReport {
+            id,
+            name,
+            iterations,
+            durations_ms,
+            row_counts,
+        });
+    }
+
+    let report = BenchmarkReport {
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        queries,
+    };
+
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| datafusion::error::DataFusionError::External(Box::new(e)))?;
+
+    match output {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// Runs `sql` and drains its result stream without writing rows anywhere, so benchmark timing stays output-format-independent.
+async fn execute_and_count_rows(ctx: &SessionContext, sql: &str) -> datafusion::error::Result<usize> {
+    autoload_dbf_tables(ctx, sql).await?;
+
+    let mut stream = ctx.sql(sql).await?.execute_stream().await?;
+    let mut row_count = 0;
+
+    while let Some(batch) = stream.next().await {
+        row_count += batch?.num_rows();
+    }
+
+    Ok(row_count)
+}
+
+async fn load_dbf_directory(ctx: &SessionContext, data_path: &str) -> datafusion::error::Result<()> {
+    for entry in std::fs::read_dir(data_path)? {
+        let path = entry?.path();
+        if path.extension().map(|ext| ext != "dbf").unwrap_or(true) {
+            continue;
         }
+
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| {
+                datafusion::error::DataFusionError::Execution(format!(
+                    "could not determine table name for {}",
+                    path.display()
+                ))
+            })?;
+
+        load_table(ctx, &path.to_string_lossy(), name).await?;
     }
+
     Ok(())
 }
 
+/// Finds quoted `.dbf` references in table position (immediately after `FROM`/`JOIN`).
+fn find_dbf_references(statement: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let bytes = statement.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let quote = bytes[i];
+        if quote == b'\'' || quote == b'"' {
+            if let Some(len) = statement[i + 1..].find(quote as char) {
+                let token = &statement[i + 1..i + 1 + len];
+                if token.to_lowercase().ends_with(".dbf")
+                    && preceded_by_from_or_join(statement, i)
+                    && !paths.iter().any(|p| p == token)
+                {
+                    paths.push(token.to_string());
+                }
+                i += len + 2;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    paths
+}
+
+fn preceded_by_from_or_join(statement: &str, quote_start: usize) -> bool {
+    let before = statement[..quote_start].trim_end();
+    let word = before
+        .rsplit(|c: char| c.is_whitespace() || c == '(' || c == ',')
+        .next()
+        .unwrap_or("");
+
+    word.eq_ignore_ascii_case("from") || word.eq_ignore_ascii_case("join")
+}
+
 async fn repl(ctx: &SessionContext, output_format: &OutputFormat) -> rustyline::Result<()> {
-    // `()` can be used when no completer is required
-    let mut rl = DefaultEditor::new()?;
-    let mut query: String = Default::default();
+    let config = Config::builder()
+        .auto_add_history(true)
+        .completion_type(CompletionType::List)
+        .history_ignore_dups(true)
+        .expect("history_ignore_dups config is always valid")
+        .build();
+
+    let mut rl = Editor::with_config(config)?;
+    rl.set_helper(Some(ReplHelper::new(ctx.clone())));
 
     let history_path = get_history_path();
 
@@ -170,15 +709,17 @@ async fn repl(ctx: &SessionContext, output_format: &OutputFormat) -> rustyline::
     loop {
         let readline = rl.readline("dbase-sql> ");
         match readline {
-            Ok(line) => {
-                rl.add_history_entry(line.as_str()).unwrap();
-                query.push_str(&line);
-                if query.ends_with(';') {
-                    process_statements(ctx, &query, output_format)
-                        .await
-                        .expect("failed to process statements");
-                    query = Default::default();
+            Ok(query) => {
+                if let Some((path, name)) = parse_load_command(&query) {
+                    if let Err(e) = load_table(ctx, &path, &name).await {
+                        println!("{}", e);
+                    }
+                    continue;
                 }
+
+                process_statements(ctx, &query, output_format)
+                    .await
+                    .expect("failed to process statements");
             }
             Err(ReadlineError::Interrupted) => {
                 println!("CTRL-C");
@@ -199,7 +740,10 @@ async fn repl(ctx: &SessionContext, output_format: &OutputFormat) -> rustyline::
     Ok(())
 }
 
-fn print_results(results: &[RecordBatch], delimiter: u8) -> Result<()> {
+async fn write_delimited_stream(
+    mut stream: SendableRecordBatchStream,
+    delimiter: u8,
+) -> datafusion::error::Result<usize> {
     let stdout = std::io::stdout();
     let mut handle = stdout.lock();
 
@@ -208,11 +752,77 @@ fn print_results(results: &[RecordBatch], delimiter: u8) -> Result<()> {
         .has_headers(true)
         .build(&mut handle);
 
-    for batch in results {
-        writer.write(batch)?;
+    let mut row_count = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        row_count += batch.num_rows();
+        writer.write(&batch)?;
     }
 
-    Ok(())
+    Ok(row_count)
+}
+
+async fn write_json_stream(
+    mut stream: SendableRecordBatchStream,
+    ndjson: bool,
+) -> datafusion::error::Result<usize> {
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    let mut row_count = 0;
+
+    if ndjson {
+        let mut writer = LineDelimitedWriter::new(&mut handle);
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            row_count += batch.num_rows();
+            writer.write(&batch)?;
+        }
+        writer.finish()?;
+    } else {
+        let mut writer = ArrayWriter::new(&mut handle);
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            row_count += batch.num_rows();
+            writer.write(&batch)?;
+        }
+        writer.finish()?;
+    }
+
+    Ok(row_count)
+}
+
+// Widths in arrow's pretty table format are computed across whatever batches
+// are handed to it at once, so printing one table per chunk keeps each
+// table's `|` boundaries internally consistent while bounding memory to a
+// chunk instead of the full result.
+const TABLE_STREAM_CHUNK_ROWS: usize = 10_000;
+
+/// Renders `stream` as one or more pretty tables, flushing every `TABLE_STREAM_CHUNK_ROWS` rows instead of buffering the whole result.
+async fn write_table_stream(mut stream: SendableRecordBatchStream) -> datafusion::error::Result<usize> {
+    let mut chunk: Vec<RecordBatch> = Vec::new();
+    let mut chunk_rows = 0;
+    let mut total_rows = 0;
+
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        chunk_rows += batch.num_rows();
+        total_rows += batch.num_rows();
+        chunk.push(batch);
+
+        if chunk_rows >= TABLE_STREAM_CHUNK_ROWS {
+            println!("{}", pretty_format_batches(&chunk)?);
+            chunk.clear();
+            chunk_rows = 0;
+        }
+    }
+
+    if !chunk.is_empty() {
+        println!("{}", pretty_format_batches(&chunk)?);
+    } else if total_rows == 0 {
+        println!("no rows");
+    }
+
+    Ok(total_rows)
 }
 
 fn get_history_path() -> String {
@@ -229,3 +839,82 @@ fn get_history_path() -> String {
 
     history_path_str.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminated_statement_requires_trailing_semicolon() {
+        assert!(ends_with_terminated_statement("select 1;"));
+        assert!(!ends_with_terminated_statement("select 1"));
+        assert!(ends_with_terminated_statement("select 1;  "));
+    }
+
+    #[test]
+    fn terminated_statement_ignores_semicolons_inside_quotes() {
+        assert!(!ends_with_terminated_statement("select ';'"));
+        assert!(ends_with_terminated_statement("select ';';"));
+        assert!(ends_with_terminated_statement("select \"it's a ;\";"));
+    }
+
+    #[test]
+    fn terminated_statement_handles_doubled_quote_escaping() {
+        assert!(ends_with_terminated_statement("select 'it''s a test';"));
+        assert!(!ends_with_terminated_statement("select 'it''s a test'"));
+    }
+
+    #[test]
+    fn terminated_statement_ignores_semicolons_inside_comments() {
+        assert!(!ends_with_terminated_statement("select 1 -- trailing ; comment"));
+        assert!(ends_with_terminated_statement("select 1 -- trailing ; comment\n;"));
+        assert!(!ends_with_terminated_statement("select /* mid ; comment */ 1"));
+        assert!(ends_with_terminated_statement("select /* mid ; comment */ 1;"));
+    }
+
+    #[test]
+    fn parses_load_command() {
+        assert_eq!(
+            parse_load_command("load customers.dbf as customers"),
+            Some(("customers.dbf".to_string(), "customers".to_string()))
+        );
+        assert_eq!(
+            parse_load_command("load   ./data/orders.dbf   as   orders  "),
+            Some(("./data/orders.dbf".to_string(), "orders".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_load_commands() {
+        assert_eq!(parse_load_command("select 1"), None);
+        assert_eq!(parse_load_command("load customers.dbf"), None);
+        assert_eq!(parse_load_command("load as customers"), None);
+        assert_eq!(parse_load_command("load customers.dbf as"), None);
+    }
+
+    #[test]
+    fn finds_dbf_references_after_from_or_join() {
+        assert_eq!(
+            find_dbf_references("SELECT * FROM 'customers.dbf'"),
+            vec!["customers.dbf".to_string()]
+        );
+        assert_eq!(
+            find_dbf_references("SELECT * FROM a JOIN \"orders.dbf\" ON a.id = orders.id"),
+            vec!["orders.dbf".to_string()]
+        );
+    }
+
+    #[test]
+    fn ignores_dbf_like_literals_not_in_table_position() {
+        assert!(find_dbf_references("SELECT * FROM t WHERE name = 'x.dbf'").is_empty());
+        assert!(find_dbf_references("SELECT 'report.dbf' AS label FROM t").is_empty());
+    }
+
+    #[test]
+    fn dedupes_repeated_dbf_references() {
+        assert_eq!(
+            find_dbf_references("SELECT * FROM 'a.dbf' JOIN 'a.dbf' ON 1 = 1"),
+            vec!["a.dbf".to_string()]
+        );
+    }
+}